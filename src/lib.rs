@@ -0,0 +1,498 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt,
+};
+
+/// Errors the in-memory Huffman engine can report instead of panicking.
+#[derive(Debug)]
+pub enum HuffmanError {
+    /// Nothing to compress — an empty input has no symbols to build a tree from.
+    EmptyInput,
+    /// A compressed container that is too short or otherwise malformed.
+    Truncated,
+    /// The serde codec failed to (de)serialize a container.
+    #[cfg(feature = "serde")]
+    Serialization,
+}
+
+impl fmt::Display for HuffmanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HuffmanError::EmptyInput => write!(f, "cannot compress empty input"),
+            HuffmanError::Truncated => write!(f, "compressed data is truncated or malformed"),
+            #[cfg(feature = "serde")]
+            HuffmanError::Serialization => write!(f, "could not (de)serialize compressed data"),
+        }
+    }
+}
+
+impl std::error::Error for HuffmanError {}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone)]
+struct BitSet {
+    bytes: Vec<u8>,
+    pos: u64,
+}
+
+impl BitSet {
+    fn new() -> Self {
+        BitSet {
+            bytes: Vec::<u8>::from([0]),
+            pos: 0,
+        }
+    }
+
+    fn from(bytes: &[u8]) -> Self {
+        BitSet {
+            bytes: Vec::<u8>::from(bytes),
+            pos: 8 * bytes.len() as u64,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn push_bit(&mut self, bit: u8) {
+        let i = (self.pos / 8) as usize;
+        let r = self.pos % 8;
+        if i == self.bytes.len() {
+            self.bytes.push(0);
+        }
+        if bit == 1 {
+            self.bytes[i] |= 1 << r;
+        }
+        self.pos += 1;
+    }
+
+    fn push_u32(&mut self, x: u32) {
+        for i in 0..32 {
+            let mask = 1 << i;
+            let bit = (x & mask).min(1) as u8;
+            self.push_bit(bit)
+        }
+    }
+
+    fn read_u32(&self, index: usize) -> u32 {
+        let mut x = 0u32;
+        for i in 0..32 {
+            x |= (self.get_bit(index + i) as u32) << i;
+        }
+        x
+    }
+
+    fn raw_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn get_bit(&self, index: usize) -> u8 {
+        let i = index / 8;
+        let r = index % 8;
+        let mask = 1 << r;
+        (self.bytes[i] & mask).min(1)
+    }
+
+    fn push_back(&mut self, rhs: BitSet) {
+        for i in 0..rhs.pos {
+            self.push_bit(rhs.get_bit(i as usize))
+        }
+    }
+}
+
+impl fmt::Display for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..self.pos {
+            f.write_str(match self.get_bit(i as usize).min(1) {
+                1 => "1",
+                0 => "0",
+                _ => panic!(""),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A node in the flat Huffman arena. Leaves carry a `data` symbol; internal
+/// nodes leave it `None` and point at their children by arena index. `parent`
+/// links back up so the tree can be walked in either direction without
+/// recursion.
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    count: u64,
+    data: Option<u8>,
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl Node {
+    fn leaf(count: u64, symbol: u8) -> Self {
+        Self {
+            count,
+            data: Some(symbol),
+            parent: None,
+            left: None,
+            right: None,
+        }
+    }
+
+    fn internal(count: u64, left: usize, right: usize) -> Self {
+        Self {
+            count,
+            data: None,
+            parent: None,
+            left: Some(left),
+            right: Some(right),
+        }
+    }
+}
+
+/// A Huffman tree stored as a flat pool of [`Node`]s with `root` indexing the
+/// top of the tree. Building and traversal use arena indices instead of boxed
+/// children, so nothing is heap-allocated per node and deep/skewed trees cannot
+/// overflow the stack.
+pub struct HuffmanTree {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+impl HuffmanTree {
+    fn code_lengths(&self) -> HashMap<u8, u32> {
+        let mut lengths = HashMap::<u8, u32>::new();
+        let mut stack = vec![(self.root, 0u32)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = self.nodes[index];
+            match node.data {
+                Some(symbol) => {
+                    lengths.insert(symbol, depth);
+                }
+                None => {
+                    if let Some(left) = node.left {
+                        stack.push((left, depth + 1));
+                    }
+                    if let Some(right) = node.right {
+                        stack.push((right, depth + 1));
+                    }
+                }
+            }
+        }
+        // A single-symbol input produces a bare leaf at depth 0; canonical
+        // coding still needs one bit to name it.
+        for len in lengths.values_mut() {
+            if *len == 0 {
+                *len = 1;
+            }
+        }
+        lengths
+    }
+}
+
+/// A canonical Huffman code: the low `bits` bits of `value`, most significant
+/// first.
+#[derive(Clone, Copy, Debug)]
+struct Code {
+    value: u64,
+    bits: u32,
+}
+
+/// Assign canonical codes from a symbol/length table. Symbols are ordered by
+/// (length, symbol value); the first symbol of the shortest length gets the
+/// all-zeros code and each subsequent code is the previous one incremented and
+/// left-shifted by the growth in length.
+fn canonical_codes(lengths: &HashMap<u8, u32>) -> HashMap<u8, Code> {
+    let mut symbols: Vec<(u8, u32)> = lengths.iter().map(|(&s, &l)| (s, l)).collect();
+    symbols.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    let mut codes = HashMap::<u8, Code>::new();
+    let mut value = 0u64;
+    let mut prev_len = 0u32;
+    for (i, &(symbol, len)) in symbols.iter().enumerate() {
+        if i != 0 {
+            value = (value + 1) << (len - prev_len);
+        }
+        codes.insert(symbol, Code { value, bits: len });
+        prev_len = len;
+    }
+    codes
+}
+
+fn count_frequency(data: &[u8]) -> HashMap<u8, u64> {
+    let mut byte_freq_table: HashMap<u8, u64> = HashMap::new();
+    for &b in data {
+        byte_freq_table
+            .entry(b)
+            .and_modify(|counter| *counter += 1)
+            .or_insert(1);
+    }
+    byte_freq_table
+}
+
+/// Build a Huffman tree from a byte frequency table. Returns `None` when the
+/// table is empty, i.e. there is nothing to encode.
+pub fn build_huffman_tree(byte_freq_table: HashMap<u8, u64>) -> Option<HuffmanTree> {
+    let mut nodes = Vec::<Node>::new();
+    // The heap orders arena indices by (count, index); `Reverse` turns the
+    // max-heap into the min-heap Huffman needs, popping the rarest nodes first.
+    let mut heap = BinaryHeap::<Reverse<(u64, usize)>>::new();
+    for (symbol, count) in byte_freq_table {
+        let index = nodes.len();
+        nodes.push(Node::leaf(count, symbol));
+        heap.push(Reverse((count, index)));
+    }
+    while heap.len() > 1 {
+        let fst = heap.pop();
+        let snd = heap.pop();
+        match (fst, snd) {
+            (Some(Reverse((_, fst_index))), Some(Reverse((_, snd_index)))) => {
+                let count = nodes[fst_index].count + nodes[snd_index].count;
+                let index = nodes.len();
+                nodes.push(Node::internal(count, fst_index, snd_index));
+                nodes[fst_index].parent = Some(index);
+                nodes[snd_index].parent = Some(index);
+                heap.push(Reverse((count, index)));
+            }
+            _ => panic!("unexpected element in heap was found"),
+        }
+    }
+    heap.pop().map(|Reverse((_, root))| HuffmanTree { nodes, root })
+}
+
+fn encode_data(data: &[u8], huffman_codes: &HashMap<u8, Code>) -> BitSet {
+    let mut data_bit_set = BitSet::new();
+    for &b in data {
+        let code = huffman_codes[&b];
+        for i in (0..code.bits).rev() {
+            data_bit_set.push_bit(((code.value >> i) & 1) as u8);
+        }
+    }
+    data_bit_set
+}
+
+/// The compressed container: the original length, the canonical length table,
+/// and the packed code bits. Both the header fields and the payload start on
+/// byte boundaries, so [`from_bytes`](Self::from_bytes) can split them without
+/// re-walking the bitstream.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompressedFile {
+    uncompressed_file_size: u32,
+    lengths: Vec<(u8, u32)>,
+    data: BitSet,
+}
+
+impl CompressedFile {
+    fn new(uncompressed_file_size: u32, lengths: Vec<(u8, u32)>, data: BitSet) -> Self {
+        CompressedFile {
+            uncompressed_file_size,
+            lengths,
+            data,
+        }
+    }
+
+    /// Serialize the container: three leading `u32` size fields, a `u32` entry
+    /// count, one `(symbol, length)` byte pair per symbol, then the payload.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header_size = 16 + (self.lengths.len() as u32) * 2;
+        let compressed_file_size = header_size + self.data.len() as u32;
+        let mut out = BitSet::new();
+        out.push_u32(compressed_file_size);
+        out.push_u32(header_size);
+        out.push_u32(self.uncompressed_file_size);
+        out.push_u32(self.lengths.len() as u32);
+        for &(symbol, len) in &self.lengths {
+            // The length table stores each code length in a single byte; canonical
+            // lengths over a 256-symbol alphabet never exceed 255, but guard the
+            // invariant so a future widening of the input can't silently truncate.
+            debug_assert!(len <= u8::MAX as u32, "code length {len} does not fit in a byte");
+            out.push_back(BitSet::from(&[symbol, len as u8]));
+        }
+        out.push_back(self.data.clone());
+        out.raw_bytes()
+    }
+
+    /// Parse a container produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, HuffmanError> {
+        if bytes.len() < 16 {
+            return Err(HuffmanError::Truncated);
+        }
+        let bits = BitSet::from(bytes);
+        let mut cursor = 0usize;
+        let _compressed_file_size = bits.read_u32(cursor);
+        cursor += 32;
+        let _header_size = bits.read_u32(cursor);
+        cursor += 32;
+        let uncompressed_file_size = bits.read_u32(cursor);
+        cursor += 32;
+        let entry_count = bits.read_u32(cursor);
+        cursor += 32;
+        let header_bytes = 16 + entry_count as usize * 2;
+        if bytes.len() < header_bytes {
+            return Err(HuffmanError::Truncated);
+        }
+        let mut lengths = Vec::<(u8, u32)>::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let mut symbol = 0u8;
+            for i in 0..8 {
+                symbol |= bits.get_bit(cursor) << i;
+                cursor += 1;
+            }
+            let mut len = 0u8;
+            for i in 0..8 {
+                len |= bits.get_bit(cursor) << i;
+                cursor += 1;
+            }
+            lengths.push((symbol, len as u32));
+        }
+        let data = BitSet::from(&bytes[header_bytes..]);
+        Ok(CompressedFile::new(uncompressed_file_size, lengths, data))
+    }
+
+    /// Rebuild the original bytes by replaying the payload through the canonical
+    /// code table derived from the stored lengths. Returns
+    /// [`HuffmanError::Truncated`] if the payload runs out before
+    /// `uncompressed_file_size` symbols have been produced.
+    fn decode(&self) -> Result<Vec<u8>, HuffmanError> {
+        // Canonical order is (length, symbol value) so per-length symbol runs
+        // are contiguous and indexable by offset from the first code of that
+        // length.
+        let mut symbols = self.lengths.clone();
+        symbols.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        let max_len = symbols.iter().map(|s| s.1).max().unwrap_or(0) as usize;
+        let mut count = vec![0u64; max_len + 1];
+        for &(_, len) in &symbols {
+            count[len as usize] += 1;
+        }
+        let mut first_code = vec![0u64; max_len + 1];
+        let mut first_index = vec![0usize; max_len + 1];
+        let mut code = 0u64;
+        let mut index = 0usize;
+        for len in 1..=max_len {
+            code = (code + count[len - 1]) << 1;
+            first_code[len] = code;
+            first_index[len] = index;
+            index += count[len] as usize;
+        }
+
+        let mut decoded = Vec::<u8>::with_capacity(self.uncompressed_file_size as usize);
+        let mut cursor = 0usize;
+        let mut code = 0u64;
+        let mut len = 0usize;
+        while (decoded.len() as u32) < self.uncompressed_file_size {
+            if (cursor as u64) >= self.data.pos {
+                return Err(HuffmanError::Truncated);
+            }
+            code = (code << 1) | self.data.get_bit(cursor) as u64;
+            cursor += 1;
+            len += 1;
+            if len <= max_len && count[len] > 0 {
+                let offset = code.wrapping_sub(first_code[len]);
+                if offset < count[len] {
+                    decoded.push(symbols[first_index[len] + offset as usize].0);
+                    code = 0;
+                    len = 0;
+                }
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl CompressedFile {
+    /// Serialize the container to `writer` with bincode's compact binary codec.
+    /// The output holds the field values only: no field names, type tags or
+    /// format version are written.
+    pub fn to_writer<W: std::io::Write>(&self, writer: W) -> Result<(), HuffmanError> {
+        bincode::serialize_into(writer, self).map_err(|_| HuffmanError::Serialization)
+    }
+
+    /// Read a container previously written by [`to_writer`](Self::to_writer).
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, HuffmanError> {
+        bincode::deserialize_from(reader).map_err(|_| HuffmanError::Serialization)
+    }
+}
+
+/// Compress `bytes` into a self-contained Huffman container.
+pub fn encode(bytes: &[u8]) -> Result<Vec<u8>, HuffmanError> {
+    let byte_freq_table = count_frequency(bytes);
+    let tree = build_huffman_tree(byte_freq_table).ok_or(HuffmanError::EmptyInput)?;
+    let lengths = tree.code_lengths();
+    let huffman_codes = canonical_codes(&lengths);
+    let mut table: Vec<(u8, u32)> = lengths.into_iter().collect();
+    table.sort_by_key(|a| a.0);
+    let data = encode_data(bytes, &huffman_codes);
+    let file = CompressedFile::new(bytes.len() as u32, table, data);
+    Ok(file.to_bytes())
+}
+
+/// Decompress a container produced by [`encode`] back into the original bytes.
+pub fn decode(bytes: &[u8]) -> Result<Vec<u8>, HuffmanError> {
+    let file = CompressedFile::from_bytes(bytes)?;
+    file.decode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(input: &[u8]) {
+        let encoded = encode(input).expect("encode should succeed for non-empty input");
+        let decoded = decode(&encoded).expect("decode should succeed");
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn round_trips_varied_inputs() {
+        round_trip(b"hello huffman world");
+        round_trip(b"aaaaaabbbbcccd");
+        round_trip(&[0u8, 255, 128, 1, 1, 1, 7, 7, 200]);
+    }
+
+    #[test]
+    fn empty_input_is_an_error() {
+        assert!(matches!(encode(&[]), Err(HuffmanError::EmptyInput)));
+    }
+
+    #[test]
+    fn single_symbol_round_trips() {
+        round_trip(&[42u8; 100]);
+    }
+
+    #[test]
+    fn full_alphabet_round_trips() {
+        let input: Vec<u8> = (0..=255).collect();
+        round_trip(&input);
+    }
+
+    #[test]
+    fn deep_codes_round_trip() {
+        // Fibonacci frequencies force a maximally skewed tree, so some symbols
+        // end up with very long canonical codes.
+        let mut counts = vec![1u64, 1];
+        while counts.len() < 24 {
+            let next = counts[counts.len() - 1] + counts[counts.len() - 2];
+            counts.push(next);
+        }
+        let mut input = Vec::new();
+        for (symbol, &count) in counts.iter().enumerate() {
+            for _ in 0..count {
+                input.push(symbol as u8);
+            }
+        }
+        round_trip(&input);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_writer_reader_round_trips() {
+        let input = b"serde huffman round trip";
+        let encoded = encode(input).expect("encode should succeed");
+        let file = CompressedFile::from_bytes(&encoded).expect("from_bytes should succeed");
+        let mut buf = Vec::new();
+        file.to_writer(&mut buf).expect("to_writer should succeed");
+        let restored = CompressedFile::from_reader(&buf[..]).expect("from_reader should succeed");
+        let decoded = restored.decode().expect("decode should succeed");
+        assert_eq!(decoded, input);
+    }
+}